@@ -0,0 +1,285 @@
+use std::any::{ Any, TypeId };
+use std::collections::{ HashMap, VecDeque };
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{ Duration, Instant };
+
+use super::{ Endpoint, Service, ServiceResult };
+
+#[derive(Clone, Debug, PartialEq)]
+/// Controls how many responses a CachingService keeps and for how long
+pub struct CacheConfig {
+    /// Responses kept before the least recently used one is evicted
+    pub capacity: usize,
+    /// How long a cached response is served before the inner service is called again
+    pub ttl: Duration,
+}
+
+impl CacheConfig {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        CacheConfig { capacity, ttl }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig::new(100, Duration::from_secs(60))
+    }
+}
+
+/// Requests are keyed by their type as well as their Debug rendering, so endpoints of different
+/// types which render alike never share an entry
+type CacheKey = (TypeId, String);
+
+struct CacheEntry {
+    stored_at: Instant,
+    response: Box<dyn Any + Send + Sync>,
+}
+
+struct Cache {
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// Keys from least to most recently used
+    order: VecDeque<CacheKey>,
+}
+
+impl Cache {
+    fn touch(&mut self, key: &CacheKey) {
+        if let Some(position) = self.order.iter().position(|k| k == key) {
+            let key = self.order.remove(position).unwrap();
+            self.order.push_back(key);
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        self.entries.remove(key);
+        self.order.retain(|k| k != key);
+    }
+}
+
+/// CachingService decorates an inner service, serving Ok responses from an in memory LRU cache
+/// keyed by the type and Debug rendering of the request. Only Ok outcomes are cached, so Err and
+/// Fail always reach the inner service again. Cached responses must be Send and Sync so the service
+/// can be shared between threads.
+/// Cached responses are cloned out of the cache, which Service::exec cannot require, so
+/// CachingService exposes its own exec rather than implementing Service.
+pub struct CachingService<S> {
+    inner: S,
+    config: CacheConfig,
+    cache: Mutex<Cache>,
+}
+
+impl<S> CachingService<S>
+where
+    S: Service,
+{
+    pub fn new(inner: S, config: CacheConfig) -> Self {
+        CachingService {
+            inner,
+            config,
+            cache: Mutex::new(Cache {
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// The number of responses currently cached, including any not yet evicted after expiring
+    pub fn len(&self) -> usize {
+        self.cache.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every cached response
+    pub fn clear(&self) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.entries.clear();
+        cache.order.clear();
+    }
+
+    /// Serves a fresh cached Ok response for the request, otherwise executes it against the inner
+    /// service and caches the outcome when it is Ok
+    pub fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, S::TServiceError, S::TErrorSerde>
+    where
+        TRequest: Into<S::TRequestType> + Endpoint + fmt::Debug + 'static,
+        TRequest::TResponse: Clone + Send + Sync + 'static,
+    {
+        let key = (TypeId::of::<TRequest>(), format!("{:?}", req));
+        if let Some(response) = self.lookup::<TRequest::TResponse>(&key) {
+            return ServiceResult::Ok(response);
+        }
+        let result = self.inner.exec(req);
+        if let ServiceResult::Ok(response) = &result {
+            self.store(key, response.clone());
+        }
+        result
+    }
+
+    fn lookup<T>(&self, key: &CacheKey) -> Option<T>
+    where
+        T: Clone + 'static,
+    {
+        let mut cache = self.cache.lock().unwrap();
+        let fresh = cache.entries.get(key)?.stored_at.elapsed() < self.config.ttl;
+        if !fresh {
+            cache.remove(key);
+            return None;
+        }
+        let response = cache.entries.get(key)?.response.downcast_ref::<T>().cloned()?;
+        cache.touch(key);
+        Some(response)
+    }
+
+    fn store<T>(&self, key: CacheKey, response: T)
+    where
+        T: Send + Sync + 'static,
+    {
+        if self.config.capacity == 0 {
+            return;
+        }
+        let mut cache = self.cache.lock().unwrap();
+        cache.remove(&key);
+        while cache.order.len() >= self.config.capacity {
+            match cache.order.pop_front() {
+                Some(evicted) => {
+                    cache.entries.remove(&evicted);
+                }
+                None => break,
+            }
+        }
+        let entry = CacheEntry {
+            stored_at: Instant::now(),
+            response: Box::new(response),
+        };
+        cache.order.push_back(key.clone());
+        cache.entries.insert(key, entry);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ CacheConfig, CachingService };
+    use test_support::{ alice, mock_user, GetUser };
+    use pretty_assertions::{ assert_eq };
+    use std::thread;
+    use std::time::Duration;
+    use { MockResponse, MockService };
+
+    fn caching(capacity: usize, ttl: Duration) -> CachingService<MockService<GetUser, String>> {
+        let svc = mock_user(alice());
+        svc.respond(&GetUser { id: 2 }, MockResponse::Ok("\"bob\"".to_owned()));
+        svc.respond(&GetUser { id: 3 }, MockResponse::Fail("timeout".to_owned()));
+        CachingService::new(svc, CacheConfig::new(capacity, ttl))
+    }
+
+    #[test]
+    fn serves_repeated_request_from_cache() {
+        let svc = caching(10, Duration::from_secs(60));
+
+        let first = svc.exec(GetUser { id: 1 });
+        let second = svc.exec(GetUser { id: 1 });
+
+        assert_eq!(Some("alice".to_owned()), first.ok());
+        assert_eq!(Some("alice".to_owned()), second.ok());
+        assert_eq!(1, svc.inner().requests().len());
+    }
+
+    #[test]
+    fn does_not_cache_fail() {
+        let svc = caching(10, Duration::from_secs(60));
+
+        assert!(svc.exec(GetUser { id: 3 }).is_fail());
+        assert!(svc.exec(GetUser { id: 3 }).is_fail());
+
+        assert_eq!(2, svc.inner().requests().len());
+        assert!(svc.is_empty());
+    }
+
+    mod other {
+        #[derive(Debug)]
+        pub struct GetUser {
+            pub id: u32,
+        }
+
+        impl ::Endpoint for GetUser {
+            type TResponse = Vec<String>;
+            type TError = String;
+        }
+    }
+
+    impl From<other::GetUser> for GetUser {
+        fn from(req: other::GetUser) -> Self {
+            GetUser { id: req.id }
+        }
+    }
+
+    #[test]
+    fn keeps_alike_requests_of_different_types_apart() {
+        let svc = caching(10, Duration::from_secs(60));
+        svc.inner().respond(&GetUser { id: 1 }, MockResponse::Ok("[\"alice\"]".to_owned()));
+
+        let user = svc.exec(GetUser { id: 1 });
+        let users = svc.exec(other::GetUser { id: 1 });
+        let cached = svc.exec(other::GetUser { id: 1 });
+
+        assert_eq!(Some("alice".to_owned()), user.ok());
+        assert_eq!(Some(vec!["alice".to_owned()]), users.ok());
+        assert_eq!(Some(vec!["alice".to_owned()]), cached.ok());
+        assert_eq!(2, svc.inner().requests().len());
+        assert_eq!(2, svc.len());
+    }
+
+    #[test]
+    fn is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<CachingService<MockService<GetUser, String>>>();
+    }
+
+    #[test]
+    fn expires_after_ttl() {
+        let svc = caching(10, Duration::from_millis(20));
+        svc.exec(GetUser { id: 1 });
+
+        thread::sleep(Duration::from_millis(30));
+        svc.exec(GetUser { id: 1 });
+
+        assert_eq!(2, svc.inner().requests().len());
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let svc = caching(2, Duration::from_secs(60));
+        svc.inner().respond(&GetUser { id: 4 }, MockResponse::Ok("\"dave\"".to_owned()));
+        svc.exec(GetUser { id: 1 });
+        svc.exec(GetUser { id: 2 });
+        svc.exec(GetUser { id: 1 });
+
+        svc.exec(GetUser { id: 4 });
+        svc.exec(GetUser { id: 1 });
+        svc.exec(GetUser { id: 2 });
+
+        let expected: Vec<String> = vec![1, 2, 4, 2]
+            .into_iter()
+            .map(|id| format!("GetUser {{ id: {} }}", id))
+            .collect();
+        assert_eq!(expected, svc.inner().requests());
+        assert_eq!(2, svc.len());
+    }
+}
@@ -0,0 +1,336 @@
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{ Duration, Instant };
+
+use super::{ Endpoint, HealthStatus, Retryable, Service, ServiceError, ServiceResult };
+
+/// CircuitOpen lets a CircuitBreakerService synthesize the server error it fast-fails with
+pub trait CircuitOpen {
+    fn circuit_open() -> Self;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Controls when a CircuitBreakerService opens and how long it waits before probing again
+pub struct CircuitConfig {
+    /// Consecutive counted Err or Fail outcomes that open the circuit
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a probe call is let through, and how long that probe
+    /// may take to report back before another is let through
+    pub cooldown: Duration,
+}
+
+impl CircuitConfig {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        CircuitConfig {
+            failure_threshold,
+            cooldown,
+        }
+    }
+}
+
+impl Default for CircuitConfig {
+    fn default() -> Self {
+        CircuitConfig::new(5, Duration::from_secs(30))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The observable states of a CircuitBreakerService
+pub enum CircuitState {
+    /// Calls are passed through to the inner service
+    Closed,
+    /// Calls fast-fail without reaching the inner service
+    Open,
+    /// The cooldown has elapsed and a single probe call decides whether the circuit closes again
+    HalfOpen,
+}
+
+struct Circuit {
+    state: CircuitState,
+    failures: u32,
+    /// When the circuit last opened or, while half open, when the probe was let through
+    since: Instant,
+}
+
+/// CircuitBreakerService decorates an inner service, fast-failing with a synthesized Fail once the
+/// configured number of consecutive Err or Fail outcomes is reached. After the cooldown one probe
+/// call is let through; success closes the circuit while failure opens it for another cooldown.
+/// A probe that has not reported back within a further cooldown, for example because it panicked,
+/// is given up on and the next call probes instead.
+/// Only server errors that are retryable or carry no status count as failures. Any other status,
+/// such as a 404, shows the backend is answering and counts as a success.
+pub struct CircuitBreakerService<S> {
+    inner: S,
+    config: CircuitConfig,
+    circuit: Mutex<Circuit>,
+    clock: Box<dyn Fn() -> Instant + Send + Sync>,
+}
+
+impl<S> CircuitBreakerService<S>
+where
+    S: Service,
+{
+    pub fn new(inner: S, config: CircuitConfig) -> Self {
+        CircuitBreakerService::with_clock(inner, config, Instant::now)
+    }
+
+    /// Reads the current time from the clock rather than `Instant::now`, so cooldowns can be
+    /// driven deterministically
+    pub fn with_clock<F>(inner: S, config: CircuitConfig, clock: F) -> Self
+    where
+        F: Fn() -> Instant + Send + Sync + 'static,
+    {
+        CircuitBreakerService {
+            inner,
+            config,
+            circuit: Mutex::new(Circuit {
+                state: CircuitState::Closed,
+                failures: 0,
+                since: clock(),
+            }),
+            clock: Box::new(clock),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn cooled_down(&self, circuit: &Circuit) -> bool {
+        (self.clock)().saturating_duration_since(circuit.since) >= self.config.cooldown
+    }
+
+    /// The current state, reporting HalfOpen once an open circuit's cooldown has elapsed
+    pub fn state(&self) -> CircuitState {
+        let circuit = self.circuit.lock().unwrap();
+        match circuit.state {
+            CircuitState::Open if self.cooled_down(&circuit) => CircuitState::HalfOpen,
+            state => state,
+        }
+    }
+
+    /// Decides whether a call may reach the inner service, moving an open circuit to half open
+    /// once its cooldown has elapsed so exactly one probe is let through per cooldown
+    fn allow(&self) -> bool {
+        let mut circuit = self.circuit.lock().unwrap();
+        match circuit.state {
+            CircuitState::Closed => true,
+            CircuitState::HalfOpen | CircuitState::Open => {
+                if !self.cooled_down(&circuit) {
+                    return false;
+                }
+                circuit.state = CircuitState::HalfOpen;
+                circuit.since = (self.clock)();
+                true
+            }
+        }
+    }
+
+    fn record(&self, succeeded: bool) {
+        let mut circuit = self.circuit.lock().unwrap();
+        if succeeded {
+            circuit.state = CircuitState::Closed;
+            circuit.failures = 0;
+            return;
+        }
+        circuit.failures = circuit.failures.saturating_add(1);
+        if circuit.state == CircuitState::HalfOpen
+            || circuit.failures >= self.config.failure_threshold
+        {
+            circuit.state = CircuitState::Open;
+            circuit.since = (self.clock)();
+        }
+    }
+}
+
+impl<S> Service for CircuitBreakerService<S>
+where
+    S: Service,
+    S::TServiceError: CircuitOpen + ServiceError + Retryable,
+{
+    type TRequestType = S::TRequestType;
+    type TServiceError = S::TServiceError;
+    type TErrorSerde = S::TErrorSerde;
+
+    fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, Self::TServiceError, Self::TErrorSerde>
+    where
+        TRequest: Into<Self::TRequestType> + Endpoint + fmt::Debug,
+    {
+        if !self.allow() {
+            return ServiceResult::Fail(S::TServiceError::circuit_open(), None);
+        }
+        let result = self.inner.exec(req);
+        let failed = match result.server_error() {
+            Some(svc_err) => svc_err.status().is_none() || svc_err.is_retryable(),
+            None => false,
+        };
+        self.record(!failed);
+        result
+    }
+
+    /// An open circuit is Down and a half open one Degraded, a closed one reports the inner health
+    fn health(&self) -> HealthStatus {
+        match self.state() {
+            CircuitState::Closed => self.inner.health(),
+            CircuitState::HalfOpen => HealthStatus::Degraded,
+            CircuitState::Open => HealthStatus::Down,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ CircuitBreakerService, CircuitConfig, CircuitState, Service };
+    use pretty_assertions::{ assert_eq };
+    use std::panic::{ self, AssertUnwindSafe };
+    use std::sync::{ Arc, Mutex };
+    use std::time::{ Duration, Instant };
+    use test_support::{ GetUser, TestError };
+    use { HealthStatus, MockResponse, MockService };
+
+    const COOLDOWN: Duration = Duration::from_secs(30);
+
+    type Breaker = CircuitBreakerService<MockService<GetUser, TestError>>;
+
+    /// A breaker whose clock only moves when the returned time is advanced
+    fn breaker() -> (Breaker, Arc<Mutex<Instant>>) {
+        let now = Arc::new(Mutex::new(Instant::now()));
+        let clock = Arc::clone(&now);
+        let config = CircuitConfig::new(2, COOLDOWN);
+        let svc = CircuitBreakerService::with_clock(MockService::new(), config, move || {
+            *clock.lock().unwrap()
+        });
+        (svc, now)
+    }
+
+    fn advance(now: &Mutex<Instant>, by: Duration) {
+        *now.lock().unwrap() += by;
+    }
+
+    #[test]
+    fn opens_after_threshold_and_fast_fails() {
+        let (svc, _) = breaker();
+        svc.inner().respond(&GetUser { id: 1 }, MockResponse::Fail(TestError::Timeout));
+
+        svc.exec(GetUser { id: 1 });
+        assert_eq!(CircuitState::Closed, svc.state());
+        assert_eq!(HealthStatus::Unknown, svc.health());
+        svc.exec(GetUser { id: 1 });
+        assert_eq!(CircuitState::Open, svc.state());
+        assert_eq!(HealthStatus::Down, svc.health());
+
+        let result = svc.exec(GetUser { id: 1 });
+
+        assert_eq!(Some(&TestError::CircuitOpen), result.server_error());
+        assert_eq!(2, svc.inner().requests().len());
+    }
+
+    #[test]
+    fn closes_after_successful_probe() {
+        let (svc, now) = breaker();
+        let req = GetUser { id: 1 };
+        svc.inner().respond(&req, MockResponse::Fail(TestError::Timeout));
+        svc.inner().respond(&req, MockResponse::Fail(TestError::Timeout));
+        svc.inner().respond(&req, MockResponse::Ok("\"alice\"".to_owned()));
+        svc.exec(GetUser { id: 1 });
+        svc.exec(GetUser { id: 1 });
+
+        advance(&now, COOLDOWN);
+        assert_eq!(CircuitState::HalfOpen, svc.state());
+        let result = svc.exec(GetUser { id: 1 });
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+        assert_eq!(CircuitState::Closed, svc.state());
+        assert_eq!(3, svc.inner().requests().len());
+    }
+
+    #[test]
+    fn reopens_after_failed_probe() {
+        let (svc, now) = breaker();
+        svc.inner().respond(&GetUser { id: 1 }, MockResponse::Fail(TestError::Timeout));
+        svc.exec(GetUser { id: 1 });
+        svc.exec(GetUser { id: 1 });
+
+        advance(&now, COOLDOWN);
+        let probe = svc.exec(GetUser { id: 1 });
+        let after = svc.exec(GetUser { id: 1 });
+
+        assert_eq!(Some(&TestError::Timeout), probe.server_error());
+        assert_eq!(Some(&TestError::CircuitOpen), after.server_error());
+        assert_eq!(CircuitState::Open, svc.state());
+        assert_eq!(3, svc.inner().requests().len());
+    }
+
+    #[test]
+    fn probes_again_after_probe_panics() {
+        let (svc, now) = breaker();
+        svc.inner().respond(&GetUser { id: 1 }, MockResponse::Fail(TestError::Timeout));
+        svc.inner().respond(&GetUser { id: 2 }, MockResponse::Ok("\"bob\"".to_owned()));
+        svc.exec(GetUser { id: 1 });
+        svc.exec(GetUser { id: 1 });
+        advance(&now, COOLDOWN);
+
+        // The mock has no response for this request, so the probe panics before recording
+        let probe = panic::catch_unwind(AssertUnwindSafe(|| svc.exec(GetUser { id: 3 })));
+        assert!(probe.is_err());
+        let stalled = svc.exec(GetUser { id: 2 });
+        advance(&now, COOLDOWN);
+        let result = svc.exec(GetUser { id: 2 });
+
+        assert_eq!(Some(&TestError::CircuitOpen), stalled.server_error());
+        assert_eq!(Some("bob".to_owned()), result.ok());
+        assert_eq!(CircuitState::Closed, svc.state());
+    }
+
+    #[test]
+    fn ignores_failures_with_non_retryable_status() {
+        let (svc, _) = breaker();
+        svc.inner().respond(&GetUser { id: 1 }, MockResponse::Fail(TestError::NotFound));
+
+        for _ in 0..4 {
+            svc.exec(GetUser { id: 1 });
+        }
+
+        assert_eq!(CircuitState::Closed, svc.state());
+        assert_eq!(4, svc.inner().requests().len());
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-enabled", feature = "mockito-enabled"))]
+mod http_test {
+    use super::{ CircuitBreakerService, CircuitConfig };
+    use pretty_assertions::{ assert_eq };
+    use std::time::Duration;
+    use test_support::GetUser;
+    use { HttpError, HttpService, TimeoutService };
+
+    #[test]
+    fn opens_on_http_server_errors_behind_timeout() {
+        let mock = mockito::mock("GET", "/users/22")
+            .with_status(503)
+            .with_body("\"unavailable\"")
+            .expect(2)
+            .create();
+        let http = HttpService::new("https://api.example.com");
+        let config = CircuitConfig::new(2, Duration::from_secs(30));
+        let svc = TimeoutService::new(CircuitBreakerService::new(http, config));
+
+        let statuses: Vec<Option<u16>> = (0..3)
+            .map(|_| match svc.exec(GetUser { id: 22 }).server_error() {
+                Some(HttpError::Status(status)) => Some(status.as_u16()),
+                Some(HttpError::CircuitOpen) => None,
+                other => panic!("expected a status or an open circuit, got {:?}", other),
+            })
+            .collect();
+
+        assert_eq!(vec![Some(503), Some(503), None], statuses);
+        mock.assert();
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::time::Duration;
+
+use super::{ BodyFormat, Endpoint, Service, ServiceResult };
+
+/// The header CorrelatedService sends the correlation id in unless configured otherwise
+pub const CORRELATION_ID_HEADER: &str = "X-Correlation-Id";
+
+#[derive(Debug)]
+/// An endpoint request carrying a correlation id, contributed as an extra header on top of the
+/// wrapped request's own headers. Every other part of the request is delegated unchanged.
+pub struct CorrelatedRequest<TRequest> {
+    req: TRequest,
+    header: String,
+    correlation_id: String,
+}
+
+impl<TRequest> CorrelatedRequest<TRequest> {
+    pub fn new(req: TRequest, header: &str, correlation_id: &str) -> Self {
+        CorrelatedRequest {
+            req,
+            header: header.to_owned(),
+            correlation_id: correlation_id.to_owned(),
+        }
+    }
+
+    pub fn correlation_id(&self) -> &str {
+        &self.correlation_id
+    }
+
+    pub fn into_inner(self) -> TRequest {
+        self.req
+    }
+}
+
+impl<TRequest> Endpoint for CorrelatedRequest<TRequest>
+where
+    TRequest: Endpoint,
+{
+    type TResponse = TRequest::TResponse;
+    type TError = TRequest::TError;
+
+    fn method(&self) -> http::Method {
+        self.req.method()
+    }
+
+    fn path(&self) -> String {
+        self.req.path()
+    }
+
+    fn query(&self) -> Vec<(String, String)> {
+        self.req.query()
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        let mut headers = self.req.headers();
+        headers.push((self.header.clone(), self.correlation_id.clone()));
+        headers
+    }
+
+    fn body(&self) -> Option<Vec<u8>> {
+        self.req.body()
+    }
+
+    fn body_format(&self) -> BodyFormat {
+        self.req.body_format()
+    }
+
+    fn accept(&self) -> &'static str {
+        self.req.accept()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.req.timeout()
+    }
+
+    fn success_status(&self) -> u16 {
+        self.req.success_status()
+    }
+}
+
+/// CorrelatedService decorates an inner service, attaching a per call correlation id to each
+/// request as a header through the Endpoint headers hook.
+/// The inner service receives a CorrelatedRequest rather than the request itself, which
+/// Service::exec cannot express, so CorrelatedService exposes its own exec rather than implementing
+/// Service.
+pub struct CorrelatedService<S> {
+    inner: S,
+    header: String,
+}
+
+impl<S> CorrelatedService<S>
+where
+    S: Service,
+{
+    /// Sends the correlation id in the X-Correlation-Id header
+    pub fn new(inner: S) -> Self {
+        CorrelatedService::with_header(inner, CORRELATION_ID_HEADER)
+    }
+
+    pub fn with_header(inner: S, header: &str) -> Self {
+        CorrelatedService {
+            inner,
+            header: header.to_owned(),
+        }
+    }
+
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Executes the request against the inner service with the correlation id attached
+    pub fn exec<TRequest>(
+        &self,
+        req: TRequest,
+        correlation_id: &str,
+    ) -> ServiceResult<TRequest, S::TServiceError, S::TErrorSerde>
+    where
+        TRequest: Endpoint + fmt::Debug,
+        CorrelatedRequest<TRequest>: Into<S::TRequestType>,
+    {
+        let req = CorrelatedRequest::new(req, &self.header, correlation_id);
+        match self.inner.exec(req) {
+            ServiceResult::Ok(response) => ServiceResult::Ok(response),
+            ServiceResult::Err(svc_err, err) => ServiceResult::Err(svc_err, err),
+            ServiceResult::Fail(svc_err, opt_serde_err) => {
+                ServiceResult::Fail(svc_err, opt_serde_err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ CorrelatedRequest, CorrelatedService, Endpoint, CORRELATION_ID_HEADER };
+    use pretty_assertions::{ assert_eq };
+    use test_support::{ alice, GetUser };
+    use MockService;
+
+    #[derive(Debug)]
+    struct GetLocalizedUser {
+        id: u32,
+    }
+
+    impl Endpoint for GetLocalizedUser {
+        type TResponse = String;
+        type TError = String;
+
+        fn path(&self) -> String {
+            format!("/users/{}", self.id)
+        }
+
+        fn headers(&self) -> Vec<(String, String)> {
+            vec![("Accept-Language".to_owned(), "en".to_owned())]
+        }
+    }
+
+    #[test]
+    fn appends_correlation_header() {
+        let user = GetLocalizedUser { id: 1 };
+        let req = CorrelatedRequest::new(user, CORRELATION_ID_HEADER, "abc-123");
+
+        let expected = vec![
+            ("Accept-Language".to_owned(), "en".to_owned()),
+            ("X-Correlation-Id".to_owned(), "abc-123".to_owned()),
+        ];
+        assert_eq!(expected, req.headers());
+        assert_eq!("/users/1", req.path());
+    }
+
+    #[test]
+    fn delegates_correlated_request() {
+        let inner: MockService<CorrelatedRequest<GetUser>, String> = MockService::new();
+        let correlated = CorrelatedRequest::new(GetUser { id: 1 }, "X-Trace-Id", "abc-123");
+        inner.respond(&correlated, alice());
+        let svc = CorrelatedService::with_header(inner, "X-Trace-Id");
+
+        let result = svc.exec(GetUser { id: 1 }, "abc-123");
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+        assert_eq!(1, svc.inner().requests().len());
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-enabled", feature = "mockito-enabled"))]
+mod http_test {
+    use super::CorrelatedService;
+    use pretty_assertions::{ assert_eq };
+    use { Endpoint, HttpService };
+
+    #[derive(Debug)]
+    struct GetOrder {
+        id: u32,
+    }
+
+    impl Endpoint for GetOrder {
+        type TResponse = String;
+        type TError = String;
+
+        fn path(&self) -> String {
+            format!("/orders/{}", self.id)
+        }
+    }
+
+    #[test]
+    fn sends_correlation_header_to_backend() {
+        let _mock = mockito::mock("GET", "/orders/1")
+            .match_header("x-correlation-id", "abc-123")
+            .with_status(200)
+            .with_body("\"shipped\"")
+            .create();
+        let svc = CorrelatedService::new(HttpService::new("https://api.example.com"));
+
+        let result = svc.exec(GetOrder { id: 1 }, "abc-123");
+
+        assert_eq!(Some("shipped".to_owned()), result.ok());
+    }
+}
@@ -0,0 +1,145 @@
+use std::fmt;
+
+use super::{ Endpoint, Retryable, Service, ServiceResult };
+
+/// FallbackService tries a request against each replica in order, moving on after a Fail or an Err
+/// whose server error is retryable. The first Ok or non retryable Err is returned, otherwise the
+/// result of the last replica tried.
+/// Replicas whose health is Down are skipped, unless every replica is Down in which case all of
+/// them are tried rather than failing without a call.
+/// Service is not object safe, so the replicas share one service type, and falling back needs to
+/// clone the request, so FallbackService exposes its own exec rather than implementing Service.
+pub struct FallbackService<S> {
+    replicas: Vec<S>,
+}
+
+impl<S> FallbackService<S>
+where
+    S: Service,
+    S::TServiceError: Retryable,
+{
+    /// Panics if no replicas are given as there would be nothing to execute requests against
+    pub fn new(replicas: Vec<S>) -> Self {
+        assert!(!replicas.is_empty(), "FallbackService needs at least one replica");
+        FallbackService { replicas }
+    }
+
+    pub fn replicas(&self) -> &[S] {
+        &self.replicas
+    }
+
+    pub fn into_replicas(self) -> Vec<S> {
+        self.replicas
+    }
+
+    /// Executes the request against each replica in turn until one settles it
+    pub fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, S::TServiceError, S::TErrorSerde>
+    where
+        TRequest: Into<S::TRequestType> + Endpoint + fmt::Debug + Clone,
+    {
+        let mut replicas: Vec<&S> = self
+            .replicas
+            .iter()
+            .filter(|replica| !replica.health().is_down())
+            .collect();
+        if replicas.is_empty() {
+            replicas = self.replicas.iter().collect();
+        }
+        let (last, replicas) = replicas.split_last().unwrap();
+        for replica in replicas {
+            let result = replica.exec(req.clone());
+            let settled = match &result {
+                ServiceResult::Ok(_) => true,
+                ServiceResult::Err(svc_err, _) => !svc_err.is_retryable(),
+                ServiceResult::Fail(_, _) => false,
+            };
+            if settled {
+                return result;
+            }
+        }
+        last.exec(req)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FallbackService;
+    use pretty_assertions::{ assert_eq };
+    use test_support::{ alice, mock_user, GetUser, TestError };
+    use { HealthStatus, MockResponse };
+
+    #[test]
+    fn falls_back_until_ok() {
+        let svc = FallbackService::new(vec![
+            mock_user(MockResponse::Fail(TestError::Timeout)),
+            mock_user(MockResponse::Fail(TestError::Timeout)),
+            mock_user(alice()),
+        ]);
+
+        let result = svc.exec(GetUser { id: 1 });
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+        for replica in svc.replicas() {
+            assert_eq!(1, replica.requests().len());
+        }
+    }
+
+    #[test]
+    fn stops_at_non_retryable_err() {
+        let svc = FallbackService::new(vec![
+            mock_user(MockResponse::Err(TestError::NotFound, "\"missing\"".to_owned())),
+            mock_user(alice()),
+        ]);
+
+        let result = svc.exec(GetUser { id: 1 });
+
+        assert_eq!(Some(&TestError::NotFound), result.server_error());
+        assert!(svc.replicas()[1].requests().is_empty());
+    }
+
+    #[test]
+    fn returns_last_fail_when_all_fail() {
+        let svc = FallbackService::new(vec![
+            mock_user(MockResponse::Fail(TestError::Timeout)),
+            mock_user(MockResponse::Fail(TestError::NotFound)),
+        ]);
+
+        let result = svc.exec(GetUser { id: 1 });
+
+        assert!(result.is_fail());
+        assert_eq!(Some(&TestError::NotFound), result.server_error());
+    }
+
+    #[test]
+    fn skips_replicas_reporting_down() {
+        let svc = FallbackService::new(vec![
+            mock_user::<TestError>(MockResponse::Ok("\"stale\"".to_owned())),
+            mock_user(alice()),
+        ]);
+        svc.replicas()[0].set_health(HealthStatus::Down);
+
+        let result = svc.exec(GetUser { id: 1 });
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+        assert!(svc.replicas()[0].requests().is_empty());
+    }
+
+    #[test]
+    fn tries_all_replicas_when_all_down() {
+        let svc = FallbackService::new(vec![
+            mock_user(MockResponse::Fail(TestError::Timeout)),
+            mock_user(alice()),
+        ]);
+        for replica in svc.replicas() {
+            replica.set_health(HealthStatus::Down);
+        }
+
+        let result = svc.exec(GetUser { id: 1 });
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+        assert_eq!(1, svc.replicas()[0].requests().len());
+    }
+}
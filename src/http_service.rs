@@ -0,0 +1,687 @@
+#[cfg(feature = "compression-enabled")]
+use std::borrow::Cow;
+use std::fmt;
+#[cfg(feature = "compression-enabled")]
+use std::io::{ self, Read };
+
+use super::{ join_url_parts, BodyFormat, CircuitOpen, Endpoint, Error, Retryable, Service };
+use super::{ ServiceError, ServiceResult, TimedOut };
+
+#[derive(Clone, Debug, PartialEq)]
+/// The transport level description of an endpoint call, built from the Endpoint metadata
+pub struct HttpRequest {
+    pub method: http::Method,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub accept: &'static str,
+    pub body: Option<Vec<u8>>,
+    pub body_format: BodyFormat,
+    pub success_status: u16,
+}
+
+impl<TEndpoint> From<TEndpoint> for HttpRequest
+where
+    TEndpoint: Endpoint,
+{
+    fn from(endpoint: TEndpoint) -> Self {
+        HttpRequest {
+            method: endpoint.method(),
+            path: endpoint.path(),
+            query: endpoint.query(),
+            headers: endpoint.headers(),
+            accept: endpoint.accept(),
+            body: endpoint.body(),
+            body_format: endpoint.body_format(),
+            success_status: endpoint.success_status(),
+        }
+    }
+}
+
+impl HttpRequest {
+    /// Whether the endpoint supplied the header itself, compared case-insensitively
+    fn has_header(&self, name: &str) -> bool {
+        self.headers.iter().any(|(header, _)| header.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug)]
+/// The stages an HttpService call can fail at
+pub enum HttpError {
+    /// The request url could not be built from the service base and endpoint
+    Url(Error),
+    /// The request could not be sent or the response body could not be read
+    Transport(reqwest::Error),
+    /// The backend responded with this status and the body did not yield the expected response
+    Status(reqwest::StatusCode),
+    #[cfg(feature = "compression-enabled")]
+    /// The response body could not be decompressed according to its Content-Encoding
+    Decode(io::Error),
+    /// A CircuitBreakerService fast-failed the call without sending it
+    CircuitOpen,
+    /// A TimeoutService gave up waiting as the call overran the endpoint's timeout
+    TimedOut,
+}
+
+impl Retryable for HttpError {
+    /// Transport failures, timeouts, throttling and server side statuses are considered transient
+    fn is_retryable(&self) -> bool {
+        match self {
+            HttpError::Url(_) => false,
+            HttpError::Transport(_) => true,
+            HttpError::Status(status) => status.is_server_error() || status.as_u16() == 429,
+            #[cfg(feature = "compression-enabled")]
+            HttpError::Decode(_) => false,
+            HttpError::CircuitOpen => false,
+            HttpError::TimedOut => true,
+        }
+    }
+}
+
+impl ServiceError for HttpError {
+    /// Only a Status carries the status the backend responded with
+    fn status(&self) -> Option<u16> {
+        match self {
+            HttpError::Status(status) => Some(status.as_u16()),
+            _ => None,
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            HttpError::Url(_) => "the request url could not be built",
+            HttpError::Transport(_) => "the request could not be sent or its response read",
+            HttpError::Status(_) => "the backend responded with an unexpected status",
+            #[cfg(feature = "compression-enabled")]
+            HttpError::Decode(_) => "the response body could not be decompressed",
+            HttpError::CircuitOpen => "the circuit is open",
+            HttpError::TimedOut => "the call overran its timeout",
+        }
+    }
+}
+
+impl CircuitOpen for HttpError {
+    fn circuit_open() -> Self {
+        HttpError::CircuitOpen
+    }
+}
+
+impl TimedOut for HttpError {
+    fn timed_out() -> Self {
+        HttpError::TimedOut
+    }
+}
+
+/// HttpService executes endpoints as JSON http calls against a base url using a blocking reqwest client.
+/// Only the endpoint's exact success status is parsed into its response, any other status,
+/// including another 2xx, into its error.
+/// Responses are routed by `ServiceResult::from_response`, so an unparseable error body is a Fail
+/// without a serde error.
+/// An empty success body is parsed as `null` so no content endpoints can respond with `()`.
+/// With compression-enabled, gzip and deflate bodies are decompressed per their Content-Encoding.
+/// `Accept-Encoding: gzip, deflate` is sent unless the endpoint provides an Accept-Encoding header.
+/// Likewise the endpoint's `accept()` is sent as Accept unless the endpoint provides that header.
+pub struct HttpService {
+    client: reqwest::blocking::Client,
+    base: String,
+}
+
+impl HttpService {
+    pub fn new(base: &str) -> Self {
+        HttpService::with_client(reqwest::blocking::Client::new(), base)
+    }
+
+    pub fn with_client(client: reqwest::blocking::Client, base: &str) -> Self {
+        HttpService {
+            client,
+            base: base.to_owned(),
+        }
+    }
+
+    /// Joins the endpoint path onto the base and runs it through `parse_url` so rebasing applies
+    fn url(&self, req: &HttpRequest) -> Result<url::Url, Error> {
+        join_url_parts(&self.base, &req.path, &req.query)
+    }
+}
+
+#[cfg(feature = "compression-enabled")]
+/// Decompresses a gzip or deflate encoded body, leaving identity and unrecognized encodings as is
+fn decode_body<'a>(encoding: Option<&str>, body: &'a [u8]) -> io::Result<Cow<'a, [u8]>> {
+    let mut decoded = Vec::new();
+    match encoding.map(str::trim) {
+        Some(encoding) if encoding.eq_ignore_ascii_case("gzip") => {
+            flate2::read::GzDecoder::new(body).read_to_end(&mut decoded)?;
+        }
+        Some(encoding) if encoding.eq_ignore_ascii_case("deflate") => {
+            flate2::read::ZlibDecoder::new(body).read_to_end(&mut decoded)?;
+        }
+        _ => return Ok(Cow::Borrowed(body)),
+    }
+    Ok(Cow::Owned(decoded))
+}
+
+/// The formats a response body can be parsed from
+enum ResponseFormat {
+    Json,
+}
+
+impl ResponseFormat {
+    /// JSON is the only format implemented so far, so every media type is parsed as JSON
+    fn from_accept(_accept: &str) -> Self {
+        ResponseFormat::Json
+    }
+
+    fn parse<T>(&self, body: &[u8]) -> Result<T, serde_json::Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        match self {
+            ResponseFormat::Json => serde_json::from_slice(body),
+        }
+    }
+}
+
+impl Service for HttpService {
+    type TRequestType = HttpRequest;
+    type TServiceError = HttpError;
+    type TErrorSerde = serde_json::Error;
+
+    fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, Self::TServiceError, Self::TErrorSerde>
+    where
+        TRequest: Into<Self::TRequestType> + Endpoint + fmt::Debug,
+    {
+        let req: HttpRequest = req.into();
+        let url = match self.url(&req) {
+            Ok(url) => url,
+            Err(err) => return ServiceResult::Fail(HttpError::Url(err), None),
+        };
+        let mut builder = self.client.request(req.method.clone(), url.as_str());
+        if !req.has_header("Accept") {
+            builder = builder.header("Accept", req.accept);
+        }
+        #[cfg(feature = "compression-enabled")]
+        {
+            if !req.has_header("Accept-Encoding") {
+                builder = builder.header("Accept-Encoding", "gzip, deflate");
+            }
+        }
+        for (name, value) in &req.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let has_content_type = req.has_header("Content-Type");
+        if let Some(body) = req.body {
+            let content_type = req.body_format.content_type().filter(|_| !has_content_type);
+            if let Some(content_type) = content_type {
+                builder = builder.header("Content-Type", content_type);
+            }
+            builder = builder.body(body);
+        }
+        let response = match builder.send() {
+            Ok(response) => response,
+            Err(err) => return ServiceResult::Fail(HttpError::Transport(err), None),
+        };
+        let status = response.status();
+        let format = ResponseFormat::from_accept(req.accept);
+        #[cfg(feature = "compression-enabled")]
+        let encoding = response
+            .headers()
+            .get("Content-Encoding")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+        let body = match response.bytes() {
+            Ok(body) => body,
+            Err(err) => return ServiceResult::Fail(HttpError::Transport(err), None),
+        };
+        #[cfg(feature = "compression-enabled")]
+        let body = match decode_body(encoding.as_deref(), &body) {
+            Ok(body) => body,
+            Err(err) => return ServiceResult::Fail(HttpError::Decode(err), None),
+        };
+        ServiceResult::from_response(
+            status.as_u16() == req.success_status,
+            HttpError::Status(status),
+            &body,
+            |body| format.parse(if body.is_empty() { b"null" } else { body }),
+            |body| format.parse(body),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "mockito-enabled"))]
+mod test {
+    use super::{ BodyFormat, CircuitOpen, Endpoint, HttpError, HttpService, Retryable, Service };
+    use super::{ ServiceError, ServiceResult, TimedOut };
+    use pretty_assertions::{ assert_eq };
+    use std::collections::HashMap;
+
+    #[derive(Debug)]
+    struct GetUser {
+        id: u32,
+    }
+
+    impl Endpoint for GetUser {
+        type TResponse = String;
+        type TError = HashMap<String, String>;
+
+        fn path(&self) -> String {
+            format!("/users/{}", self.id)
+        }
+    }
+
+    #[derive(Debug)]
+    struct GetVersionedUser {
+        id: u32,
+    }
+
+    impl Endpoint for GetVersionedUser {
+        type TResponse = String;
+        type TError = HashMap<String, String>;
+
+        fn path(&self) -> String {
+            format!("/users/{}", self.id)
+        }
+
+        fn headers(&self) -> Vec<(String, String)> {
+            vec![("X-Api-Version".to_owned(), "2".to_owned())]
+        }
+    }
+
+    fn status(result: &ServiceResult<GetUser, HttpError, serde_json::Error>) -> Option<u16> {
+        match result.server_error() {
+            Some(HttpError::Status(status)) => Some(status.as_u16()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn synthesizes_non_retryable_circuit_open() {
+        let err = HttpError::circuit_open();
+
+        assert!(matches!(err, HttpError::CircuitOpen));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn synthesizes_retryable_timed_out() {
+        let err = HttpError::timed_out();
+
+        assert!(matches!(err, HttpError::TimedOut));
+        assert!(err.is_retryable());
+        assert_eq!(None, err.status());
+    }
+
+    #[test]
+    fn parses_success_body() {
+        let _mock = mockito::mock("GET", "/users/1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("\"alice\"")
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetUser { id: 1 });
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+    }
+
+    #[test]
+    fn parses_error_body() {
+        let _mock = mockito::mock("GET", "/users/2")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body("{\"message\":\"missing\"}")
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetUser { id: 2 });
+
+        assert!(result.is_err());
+        assert_eq!(Some(404), status(&result));
+        assert_eq!(Some(404), result.server_error().and_then(ServiceError::status));
+        let message = result.service_error().and_then(|err| err.get("message")).cloned();
+        assert_eq!(Some("missing".to_owned()), message);
+    }
+
+    #[test]
+    fn fails_on_unparseable_error_body() {
+        let _mock = mockito::mock("GET", "/users/3")
+            .with_status(400)
+            .with_body("oops")
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetUser { id: 3 });
+
+        assert!(result.is_fail());
+        assert_eq!(Some(400), status(&result));
+    }
+
+    #[test]
+    fn sends_endpoint_headers() {
+        let _mock = mockito::mock("GET", "/users/4")
+            .match_header("x-api-version", "2")
+            .with_status(200)
+            .with_body("\"dave\"")
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetVersionedUser { id: 4 });
+
+        assert_eq!(Some("dave".to_owned()), result.ok());
+    }
+
+    #[derive(Debug)]
+    struct GetVendorUser {
+        id: u32,
+    }
+
+    impl Endpoint for GetVendorUser {
+        type TResponse = String;
+        type TError = HashMap<String, String>;
+
+        fn path(&self) -> String {
+            format!("/users/{}", self.id)
+        }
+
+        fn headers(&self) -> Vec<(String, String)> {
+            vec![("accept".to_owned(), "application/vnd.api+json".to_owned())]
+        }
+    }
+
+    #[test]
+    fn sends_endpoint_accept_instead_of_default() {
+        // mockito matches a header only when every value sent for it matches, so a second
+        // default Accept value would leave the request unmatched
+        let mock = mockito::mock("GET", "/users/12")
+            .match_header("accept", "application/vnd.api+json")
+            .with_status(200)
+            .with_body("\"kate\"")
+            .expect(1)
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetVendorUser { id: 12 });
+
+        assert_eq!(Some("kate".to_owned()), result.ok());
+        mock.assert();
+    }
+
+    #[derive(Debug)]
+    struct CreateUser {
+        name: String,
+    }
+
+    impl Endpoint for CreateUser {
+        type TResponse = String;
+        type TError = HashMap<String, String>;
+
+        fn method(&self) -> http::Method {
+            http::Method::POST
+        }
+
+        fn path(&self) -> String {
+            "/users".to_owned()
+        }
+
+        fn body(&self) -> Option<Vec<u8>> {
+            serde_json::to_vec(&serde_json::json!({ "name": self.name })).ok()
+        }
+
+        fn success_status(&self) -> u16 {
+            201
+        }
+    }
+
+    #[derive(Debug)]
+    struct DeleteUser {
+        id: u32,
+    }
+
+    impl Endpoint for DeleteUser {
+        type TResponse = ();
+        type TError = HashMap<String, String>;
+
+        fn method(&self) -> http::Method {
+            http::Method::DELETE
+        }
+
+        fn path(&self) -> String {
+            format!("/users/{}", self.id)
+        }
+
+        fn success_status(&self) -> u16 {
+            204
+        }
+    }
+
+    #[derive(Debug)]
+    struct Login {
+        user: String,
+        password: String,
+    }
+
+    impl Endpoint for Login {
+        type TResponse = String;
+        type TError = HashMap<String, String>;
+
+        fn method(&self) -> http::Method {
+            http::Method::POST
+        }
+
+        fn path(&self) -> String {
+            "/login".to_owned()
+        }
+
+        fn body(&self) -> Option<Vec<u8>> {
+            let fields = [("user", self.user.as_str()), ("password", self.password.as_str())];
+            self.body_format().encode(&fields[..])
+        }
+
+        fn body_format(&self) -> BodyFormat {
+            BodyFormat::Form
+        }
+    }
+
+    #[test]
+    fn sends_form_encoded_body() {
+        let _mock = mockito::mock("POST", "/login")
+            .match_header("content-type", "application/x-www-form-urlencoded")
+            .match_body("user=erin&password=s3cret%21")
+            .with_status(200)
+            .with_body("\"token\"")
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(Login {
+            user: "erin".to_owned(),
+            password: "s3cret!".to_owned(),
+        });
+
+        assert_eq!(Some("token".to_owned()), result.ok());
+    }
+
+    #[test]
+    fn parses_created_body() {
+        let _mock = mockito::mock("POST", "/users")
+            .with_status(201)
+            .with_body("\"erin\"")
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(CreateUser { name: "erin".to_owned() });
+
+        assert_eq!(Some("erin".to_owned()), result.ok());
+    }
+
+    #[test]
+    fn accepts_no_content_delete() {
+        let _mock = mockito::mock("DELETE", "/users/5").with_status(204).create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(DeleteUser { id: 5 });
+
+        assert_eq!(Some(()), result.ok());
+    }
+
+    #[test]
+    fn treats_other_success_status_as_error() {
+        let _mock = mockito::mock("DELETE", "/users/6")
+            .with_status(200)
+            .with_body("{\"message\":\"queued\"}")
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(DeleteUser { id: 6 });
+
+        assert!(result.is_err());
+        let message = result.service_error().and_then(|err| err.get("message")).cloned();
+        assert_eq!(Some("queued".to_owned()), message);
+    }
+
+    #[test]
+    fn fails_on_no_content_for_ok_endpoint() {
+        let _mock = mockito::mock("GET", "/users/7").with_status(204).create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetUser { id: 7 });
+
+        assert!(result.is_fail());
+        assert_eq!(Some(204), status(&result));
+    }
+
+    #[derive(Debug)]
+    struct GetApiUser {
+        id: u32,
+    }
+
+    impl Endpoint for GetApiUser {
+        type TResponse = String;
+        type TError = HashMap<String, String>;
+
+        fn path(&self) -> String {
+            format!("/users/{}", self.id)
+        }
+
+        fn accept(&self) -> &'static str {
+            "application/vnd.api+json"
+        }
+    }
+
+    #[test]
+    fn sends_endpoint_accept() {
+        let _mock = mockito::mock("GET", "/users/7")
+            .match_header("accept", "application/vnd.api+json")
+            .with_status(200)
+            .with_body("\"grace\"")
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetApiUser { id: 7 });
+
+        assert_eq!(Some("grace".to_owned()), result.ok());
+    }
+
+    #[cfg(feature = "compression-enabled")]
+    fn gzip(body: &str) -> Vec<u8> {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[cfg(feature = "compression-enabled")]
+    fn deflate(body: &str) -> Vec<u8> {
+        use flate2::write::ZlibEncoder;
+        use std::io::Write;
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[cfg(feature = "compression-enabled")]
+    #[test]
+    fn decompresses_gzip_success_body() {
+        let _mock = mockito::mock("GET", "/users/8")
+            .match_header("accept-encoding", "gzip, deflate")
+            .with_status(200)
+            .with_header("content-encoding", "gzip")
+            .with_body(gzip("\"grace\""))
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetUser { id: 8 });
+
+        assert_eq!(Some("grace".to_owned()), result.ok());
+    }
+
+    #[cfg(feature = "compression-enabled")]
+    #[test]
+    fn decompresses_deflate_success_body() {
+        let _mock = mockito::mock("GET", "/users/9")
+            .with_status(200)
+            .with_header("content-encoding", "deflate")
+            .with_body(deflate("\"heidi\""))
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetUser { id: 9 });
+
+        assert_eq!(Some("heidi".to_owned()), result.ok());
+    }
+
+    #[cfg(feature = "compression-enabled")]
+    #[test]
+    fn reads_identity_success_body() {
+        let _mock = mockito::mock("GET", "/users/10")
+            .match_header("accept-encoding", "gzip, deflate")
+            .with_status(200)
+            .with_body("\"ivan\"")
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetUser { id: 10 });
+
+        assert_eq!(Some("ivan".to_owned()), result.ok());
+    }
+
+    #[cfg(feature = "compression-enabled")]
+    #[derive(Debug)]
+    struct GetUncompressedUser {
+        id: u32,
+    }
+
+    #[cfg(feature = "compression-enabled")]
+    impl Endpoint for GetUncompressedUser {
+        type TResponse = String;
+        type TError = HashMap<String, String>;
+
+        fn path(&self) -> String {
+            format!("/users/{}", self.id)
+        }
+
+        fn headers(&self) -> Vec<(String, String)> {
+            vec![("accept-encoding".to_owned(), "identity".to_owned())]
+        }
+    }
+
+    #[cfg(feature = "compression-enabled")]
+    #[test]
+    fn keeps_endpoint_accept_encoding() {
+        let _mock = mockito::mock("GET", "/users/11")
+            .match_header("accept-encoding", "identity")
+            .with_status(200)
+            .with_body("\"judy\"")
+            .create();
+        let svc = HttpService::new("https://api.example.com");
+
+        let result = svc.exec(GetUncompressedUser { id: 11 });
+
+        assert_eq!(Some("judy".to_owned()), result.ok());
+    }
+}
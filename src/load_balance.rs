@@ -0,0 +1,135 @@
+use std::fmt;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+
+use super::{ Endpoint, HealthStatus, Service, ServiceResult };
+
+/// LoadBalancedService spreads requests across interchangeable replicas in round robin order,
+/// advancing a relaxed atomic counter per call so it can be shared between threads.
+/// A replica whose health is Down is passed over for the next one in order. When every replica is
+/// Down the request goes to the replica whose turn it is anyway rather than failing without a call.
+/// Service is not object safe, so the replicas share one service type.
+pub struct LoadBalancedService<S> {
+    replicas: Vec<S>,
+    next: AtomicUsize,
+}
+
+impl<S> LoadBalancedService<S>
+where
+    S: Service,
+{
+    /// Panics if no replicas are given as there would be nothing to execute requests against
+    pub fn new(replicas: Vec<S>) -> Self {
+        assert!(!replicas.is_empty(), "LoadBalancedService needs at least one replica");
+        LoadBalancedService {
+            replicas,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn replicas(&self) -> &[S] {
+        &self.replicas
+    }
+
+    pub fn into_replicas(self) -> Vec<S> {
+        self.replicas
+    }
+
+    fn select(&self) -> &S {
+        let len = self.replicas.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % len;
+        (0..len)
+            .map(|offset| &self.replicas[(start + offset) % len])
+            .find(|replica| !replica.health().is_down())
+            .unwrap_or(&self.replicas[start])
+    }
+}
+
+impl<S> Service for LoadBalancedService<S>
+where
+    S: Service,
+{
+    type TRequestType = S::TRequestType;
+    type TServiceError = S::TServiceError;
+    type TErrorSerde = S::TErrorSerde;
+
+    fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, Self::TServiceError, Self::TErrorSerde>
+    where
+        TRequest: Into<Self::TRequestType> + Endpoint + fmt::Debug,
+    {
+        self.select().exec(req)
+    }
+
+    /// Down when every replica is Down, Degraded when only some are, Up when all are Up
+    fn health(&self) -> HealthStatus {
+        let health: Vec<HealthStatus> = self.replicas.iter().map(Service::health).collect();
+        let down = health.iter().filter(|status| status.is_down()).count();
+        if down == health.len() {
+            HealthStatus::Down
+        } else if down > 0 {
+            HealthStatus::Degraded
+        } else if health.iter().all(|status| *status == HealthStatus::Up) {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Unknown
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ LoadBalancedService, Service };
+    use pretty_assertions::{ assert_eq };
+    use test_support::{ alice, mock_user, GetUser };
+    use { HealthStatus, MockService };
+
+    fn balancer() -> LoadBalancedService<MockService<GetUser, String>> {
+        let replicas = (0..3).map(|_| mock_user(alice())).collect();
+        LoadBalancedService::new(replicas)
+    }
+
+    fn calls(svc: &LoadBalancedService<MockService<GetUser, String>>) -> Vec<usize> {
+        svc.replicas().iter().map(|replica| replica.requests().len()).collect()
+    }
+
+    #[test]
+    fn rotates_across_replicas() {
+        let svc = balancer();
+
+        for _ in 0..4 {
+            assert_eq!(Some("alice".to_owned()), svc.exec(GetUser { id: 1 }).ok());
+        }
+
+        assert_eq!(vec![2, 1, 1], calls(&svc));
+    }
+
+    #[test]
+    fn skips_replicas_reporting_down() {
+        let svc = balancer();
+        svc.replicas()[1].set_health(HealthStatus::Down);
+
+        for _ in 0..3 {
+            svc.exec(GetUser { id: 1 });
+        }
+
+        assert_eq!(vec![1, 0, 2], calls(&svc));
+        assert_eq!(HealthStatus::Degraded, svc.health());
+    }
+
+    #[test]
+    fn falls_back_to_rotation_when_all_down() {
+        let svc = balancer();
+        for replica in svc.replicas() {
+            replica.set_health(HealthStatus::Down);
+        }
+
+        for _ in 0..3 {
+            svc.exec(GetUser { id: 1 });
+        }
+
+        assert_eq!(vec![1, 1, 1], calls(&svc));
+        assert_eq!(HealthStatus::Down, svc.health());
+    }
+}
@@ -0,0 +1,114 @@
+/// Defines an endpoint request struct together with its Endpoint impl.
+/// The struct derives Debug, and `{field}` placeholders in the path template are substituted with
+/// the Display rendering of the matching field, so every field must implement Display. Values are
+/// percent-encoded by `encode_path_segment` so each fills exactly one path segment.
+///
+/// # Panics
+///
+/// The generated `path` panics when a field renders as `.` or `..`, as `encode_path_segment` does.
+///
+/// ```ignore
+/// endpoint! {
+///     /// Fetches a single user
+///     pub struct GetUser { pub id: u32 }
+///     GET "/users/{id}" => User, ApiError
+/// }
+/// ```
+#[macro_export]
+macro_rules! endpoint {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident { $($field_vis:vis $field:ident : $field_ty:ty),* $(,)? }
+        $method:ident $path:literal => $response:ty, $error:ty
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        $vis struct $name {
+            $($field_vis $field: $field_ty),*
+        }
+
+        impl $crate::Endpoint for $name {
+            type TResponse = $response;
+            type TError = $error;
+
+            fn method(&self) -> $crate::HttpMethod {
+                $crate::HttpMethod::$method
+            }
+
+            fn path(&self) -> String {
+                #[allow(unused_mut)]
+                let mut path = String::from($path);
+                $(
+                    let placeholder = concat!("{", stringify!($field), "}");
+                    let value = $crate::encode_path_segment(&self.$field.to_string());
+                    path = path.replace(placeholder, &value);
+                )*
+                path
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::{ assert_eq };
+    use Endpoint;
+
+    endpoint! {
+        /// Fetches a single user
+        pub struct GetUser { pub id: u32 }
+        GET "/users/{id}" => String, String
+    }
+
+    endpoint! {
+        struct DeleteRepo { owner: String, name: String, }
+        DELETE "/repos/{owner}/{name}" => (), String
+    }
+
+    endpoint! {
+        struct ListUsers {}
+        GET "/users" => Vec<String>, String
+    }
+
+    #[test]
+    fn substitutes_path_parameters() {
+        let req = GetUser { id: 7 };
+        assert_eq!("/users/7", req.path());
+        assert_eq!(http::Method::GET, req.method());
+    }
+
+    #[test]
+    fn substitutes_multiple_path_parameters() {
+        let req = DeleteRepo {
+            owner: "omnivers3".to_owned(),
+            name: "gateway".to_owned(),
+        };
+        assert_eq!("/repos/omnivers3/gateway", req.path());
+        assert_eq!(http::Method::DELETE, req.method());
+    }
+
+    #[test]
+    fn encodes_path_parameters_as_single_segments() {
+        let req = DeleteRepo {
+            owner: "../admin".to_owned(),
+            name: "a/b?force=1#top".to_owned(),
+        };
+        assert_eq!("/repos/..%2Fadmin/a%2Fb%3Fforce=1%23top", req.path());
+    }
+
+    #[test]
+    #[should_panic(expected = "dot segment")]
+    fn rejects_dot_segment_path_parameters() {
+        let req = DeleteRepo {
+            owner: "..".to_owned(),
+            name: "gateway".to_owned(),
+        };
+        req.path();
+    }
+
+    #[test]
+    fn keeps_path_without_parameters() {
+        assert_eq!("/users", ListUsers {}.path());
+        assert_eq!("ListUsers", format!("{:?}", ListUsers {}));
+    }
+}
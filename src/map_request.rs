@@ -0,0 +1,89 @@
+use std::fmt;
+
+use super::{ Endpoint, Service, ServiceResult };
+
+/// MapRequestService adapts requests for an inner service, applying `F` to turn each outer
+/// request into a request the inner service accepts before delegating. The mapped request must
+/// share the outer request's response and error types, so its result is typed by the outer request.
+/// Service::exec cannot tie the mapped request to the outer one, so MapRequestService exposes its
+/// own exec rather than implementing Service.
+pub struct MapRequestService<S, F> {
+    inner: S,
+    map: F,
+}
+
+impl<S, F> MapRequestService<S, F>
+where
+    S: Service,
+{
+    pub fn new(inner: S, map: F) -> Self {
+        MapRequestService { inner, map }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Maps the request and executes it against the inner service
+    pub fn exec<TRequest, TMapped>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, S::TServiceError, S::TErrorSerde>
+    where
+        F: Fn(TRequest) -> TMapped,
+        TRequest: Endpoint,
+        TMapped: Into<S::TRequestType>
+            + Endpoint<TResponse = TRequest::TResponse, TError = TRequest::TError>
+            + fmt::Debug,
+    {
+        match self.inner.exec((self.map)(req)) {
+            ServiceResult::Ok(response) => ServiceResult::Ok(response),
+            ServiceResult::Err(svc_err, err) => ServiceResult::Err(svc_err, err),
+            ServiceResult::Fail(svc_err, opt_serde_err) => {
+                ServiceResult::Fail(svc_err, opt_serde_err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ Endpoint, MapRequestService };
+    use pretty_assertions::{ assert_eq };
+    use test_support::{ alice, GetUser };
+    use MockService;
+
+    #[derive(Debug)]
+    struct LegacyGetUser {
+        user_id: String,
+    }
+
+    impl Endpoint for LegacyGetUser {
+        type TResponse = String;
+        type TError = String;
+
+        fn path(&self) -> String {
+            format!("/user?id={}", self.user_id)
+        }
+    }
+
+    #[test]
+    fn delegates_mapped_request() {
+        let inner: MockService<GetUser, String> = MockService::new();
+        inner.respond(&GetUser { id: 7 }, alice());
+        let svc = MapRequestService::new(inner, |req: LegacyGetUser| GetUser {
+            id: req.user_id.parse().unwrap(),
+        });
+
+        let result = svc.exec(LegacyGetUser {
+            user_id: "7".to_owned(),
+        });
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+        assert_eq!(vec!["GetUser { id: 7 }".to_owned()], svc.inner().requests());
+    }
+}
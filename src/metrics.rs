@@ -0,0 +1,105 @@
+use std::fmt;
+use std::sync::atomic::{ AtomicU64, Ordering };
+
+use super::{ Endpoint, HealthStatus, Service, ServiceResult };
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// The outcome totals a MeteredService has counted so far
+pub struct MetricsSnapshot {
+    pub ok: u64,
+    pub err: u64,
+    pub fail: u64,
+}
+
+/// MeteredService decorates an inner service, counting each outcome by variant with relaxed atomic
+/// increments so the hot path takes no locks.
+pub struct MeteredService<S> {
+    inner: S,
+    ok: AtomicU64,
+    err: AtomicU64,
+    fail: AtomicU64,
+}
+
+impl<S> MeteredService<S>
+where
+    S: Service,
+{
+    pub fn new(inner: S) -> Self {
+        MeteredService {
+            inner,
+            ok: AtomicU64::new(0),
+            err: AtomicU64::new(0),
+            fail: AtomicU64::new(0),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// The totals counted so far; concurrent calls may land between the individual reads
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            ok: self.ok.load(Ordering::Relaxed),
+            err: self.err.load(Ordering::Relaxed),
+            fail: self.fail.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<S> Service for MeteredService<S>
+where
+    S: Service,
+{
+    type TRequestType = S::TRequestType;
+    type TServiceError = S::TServiceError;
+    type TErrorSerde = S::TErrorSerde;
+
+    fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, Self::TServiceError, Self::TErrorSerde>
+    where
+        TRequest: Into<Self::TRequestType> + Endpoint + fmt::Debug,
+    {
+        let result = self.inner.exec(req);
+        let counter = match &result {
+            ServiceResult::Ok(_) => &self.ok,
+            ServiceResult::Err(_, _) => &self.err,
+            ServiceResult::Fail(_, _) => &self.fail,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    fn health(&self) -> HealthStatus {
+        self.inner.health()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ MeteredService, MetricsSnapshot, Service };
+    use pretty_assertions::{ assert_eq };
+    use test_support::{ alice, mock_user, GetUser };
+    use MockResponse;
+
+    #[test]
+    fn counts_outcomes_by_variant() {
+        let svc = MeteredService::new(mock_user::<String>(alice()));
+        let missing = MockResponse::Err("404".to_owned(), "\"missing\"".to_owned());
+        svc.inner().respond(&GetUser { id: 2 }, missing);
+        svc.inner().respond(&GetUser { id: 3 }, MockResponse::Fail("timeout".to_owned()));
+        assert_eq!(MetricsSnapshot::default(), svc.snapshot());
+
+        for id in &[1, 1, 2, 3, 3, 3] {
+            svc.exec(GetUser { id: *id });
+        }
+
+        assert_eq!(MetricsSnapshot { ok: 2, err: 1, fail: 3 }, svc.snapshot());
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::{ HashMap, VecDeque };
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+use super::{ Endpoint, HealthStatus, Service, ServiceResult };
+
+#[derive(Clone, Debug)]
+/// The canned outcome a MockService replays, with bodies provided as raw JSON
+pub enum MockResponse<TServiceError> {
+    /// Responds with the body deserialized into the endpoint's TResponse
+    Ok(String),
+    /// Responds with the server error and the body deserialized into the endpoint's TError
+    Err(TServiceError, String),
+    /// Responds with the server error and no body
+    Fail(TServiceError),
+}
+
+/// MockService replays canned responses keyed by the Debug rendering of the request and records
+/// every request it receives. Responses registered for the same request are replayed in order,
+/// with the last one repeating for any further calls.
+pub struct MockService<TRequestType, TServiceError> {
+    responses: Mutex<HashMap<String, VecDeque<MockResponse<TServiceError>>>>,
+    requests: Mutex<Vec<String>>,
+    health: Mutex<HealthStatus>,
+    request_type: PhantomData<fn(TRequestType)>,
+}
+
+impl<TRequestType, TServiceError> MockService<TRequestType, TServiceError> {
+    pub fn new() -> Self {
+        MockService {
+            responses: Mutex::new(HashMap::new()),
+            requests: Mutex::new(Vec::new()),
+            health: Mutex::new(HealthStatus::Unknown),
+            request_type: PhantomData,
+        }
+    }
+
+    /// Queues a response for requests whose Debug rendering matches `req`
+    pub fn respond<TRequest>(&self, req: &TRequest, response: MockResponse<TServiceError>)
+    where
+        TRequest: fmt::Debug,
+    {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(format!("{:?}", req))
+            .or_default()
+            .push_back(response);
+    }
+
+    /// The Debug rendering of every request received so far, in call order
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Sets the health the mock reports, which starts out Unknown
+    pub fn set_health(&self, health: HealthStatus) {
+        *self.health.lock().unwrap() = health;
+    }
+}
+
+impl<TRequestType, TServiceError> Default for MockService<TRequestType, TServiceError> {
+    fn default() -> Self {
+        MockService::new()
+    }
+}
+
+impl<TRequestType, TServiceError> MockService<TRequestType, TServiceError>
+where
+    TServiceError: Clone,
+{
+    fn next_response(&self, key: &str) -> Option<MockResponse<TServiceError>> {
+        let mut responses = self.responses.lock().unwrap();
+        let queue = responses.get_mut(key)?;
+        if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        }
+    }
+}
+
+impl<TRequestType, TServiceError> Service for MockService<TRequestType, TServiceError>
+where
+    TServiceError: Clone,
+{
+    type TRequestType = TRequestType;
+    type TServiceError = TServiceError;
+    type TErrorSerde = serde_json::Error;
+
+    fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, Self::TServiceError, Self::TErrorSerde>
+    where
+        TRequest: Into<Self::TRequestType> + Endpoint + fmt::Debug,
+    {
+        let key = format!("{:?}", req);
+        self.requests.lock().unwrap().push(key.clone());
+        let response = self
+            .next_response(&key)
+            .unwrap_or_else(|| panic!("MockService has no response for [{}]", key));
+        match response {
+            MockResponse::Ok(body) => match serde_json::from_str(&body) {
+                Ok(response) => ServiceResult::Ok(response),
+                Err(err) => panic!("MockService response for [{}] is invalid: {}", key, err),
+            },
+            MockResponse::Err(svc_err, body) => match serde_json::from_str(&body) {
+                Ok(err) => ServiceResult::Err(svc_err, err),
+                Err(serde_err) => ServiceResult::Fail(svc_err, Some(serde_err)),
+            },
+            MockResponse::Fail(svc_err) => ServiceResult::Fail(svc_err, None),
+        }
+    }
+
+    fn health(&self) -> HealthStatus {
+        *self.health.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ MockResponse, MockService, Service };
+    use pretty_assertions::{ assert_eq };
+    use test_support::{ alice, call, GetUser };
+
+    #[test]
+    fn replays_registered_responses() {
+        let svc: MockService<GetUser, String> = MockService::new();
+        svc.respond(&GetUser { id: 1 }, alice());
+        svc.respond(&GetUser { id: 2 }, MockResponse::Fail("timeout".to_owned()));
+
+        let ok = call(&svc, GetUser { id: 1 });
+        let fail = call(&svc, GetUser { id: 2 });
+
+        assert_eq!(Some("alice".to_owned()), ok.ok());
+        assert!(fail.is_fail());
+        assert_eq!(Some(&"timeout".to_owned()), fail.server_error());
+        assert_eq!(
+            vec!["GetUser { id: 1 }".to_owned(), "GetUser { id: 2 }".to_owned()],
+            svc.requests()
+        );
+    }
+
+    #[test]
+    fn replays_queued_responses_in_order() {
+        let svc: MockService<GetUser, String> = MockService::new();
+        let req = GetUser { id: 1 };
+        svc.respond(&req, MockResponse::Fail("timeout".to_owned()));
+        svc.respond(&req, alice());
+
+        assert!(call(&svc, GetUser { id: 1 }).is_fail());
+        assert!(call(&svc, GetUser { id: 1 }).is_ok());
+        assert!(call(&svc, GetUser { id: 1 }).is_ok());
+    }
+
+    #[test]
+    fn fails_on_unparseable_error_body() {
+        let svc: MockService<GetUser, String> = MockService::new();
+        svc.respond(&GetUser { id: 1 }, MockResponse::Err("404".to_owned(), "nope".to_owned()));
+
+        let result = call(&svc, GetUser { id: 1 });
+
+        assert!(result.is_fail());
+    }
+
+    #[test]
+    fn exec_batch_preserves_request_order() {
+        let svc: MockService<GetUser, String> = MockService::new();
+        svc.respond(&GetUser { id: 1 }, alice());
+        svc.respond(&GetUser { id: 2 }, MockResponse::Fail("timeout".to_owned()));
+        svc.respond(&GetUser { id: 3 }, MockResponse::Ok("\"carol\"".to_owned()));
+
+        let results = svc.exec_batch(vec![GetUser { id: 1 }, GetUser { id: 2 }, GetUser { id: 3 }]);
+
+        assert_eq!(3, results.len());
+        assert_eq!(Some(&"alice".to_owned()), results[0].ok_ref());
+        assert!(results[1].is_fail());
+        assert_eq!(Some(&"timeout".to_owned()), results[1].server_error());
+        assert_eq!(Some(&"carol".to_owned()), results[2].ok_ref());
+        assert_eq!(
+            vec![
+                "GetUser { id: 1 }".to_owned(),
+                "GetUser { id: 2 }".to_owned(),
+                "GetUser { id: 3 }".to_owned(),
+            ],
+            svc.requests()
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::{ Endpoint, Service, ServiceResult };
+
+/// NullService never reaches a backend, answering every request with a clone of its canned server
+/// error as a Fail. Ok and Err payloads are tied to each request's endpoint type, so the request
+/// independent Fail is the only result that can be canned for any request. Useful for wiring,
+/// benchmarks and disabled feature paths.
+pub struct NullService<TRequestType, TServiceError, TErrorSerde> {
+    server_error: TServiceError,
+    request_type: PhantomData<fn(TRequestType) -> TErrorSerde>,
+}
+
+impl<TRequestType, TServiceError, TErrorSerde> NullService<TRequestType, TServiceError, TErrorSerde>
+where
+    TServiceError: Clone,
+{
+    pub fn new(server_error: TServiceError) -> Self {
+        NullService {
+            server_error,
+            request_type: PhantomData,
+        }
+    }
+
+    /// The server error every call fails with
+    pub fn server_error(&self) -> &TServiceError {
+        &self.server_error
+    }
+}
+
+impl<TRequestType, TServiceError, TErrorSerde> Service
+    for NullService<TRequestType, TServiceError, TErrorSerde>
+where
+    TServiceError: Clone,
+{
+    type TRequestType = TRequestType;
+    type TServiceError = TServiceError;
+    type TErrorSerde = TErrorSerde;
+
+    fn exec<TRequest>(
+        &self,
+        _req: TRequest,
+    ) -> ServiceResult<TRequest, Self::TServiceError, Self::TErrorSerde>
+    where
+        TRequest: Into<Self::TRequestType> + Endpoint + fmt::Debug,
+    {
+        ServiceResult::Fail(self.server_error.clone(), None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ NullService, ServiceResult };
+    use pretty_assertions::{ assert_eq };
+    use test_support::{ call, GetUser };
+
+    #[test]
+    fn returns_canned_error_repeatedly() {
+        let svc: NullService<GetUser, String, ()> = NullService::new("disabled".to_owned());
+
+        for id in 0..3 {
+            let result = call(&svc, GetUser { id });
+            assert_eq!(ServiceResult::Fail("disabled".to_owned(), None), result);
+        }
+        assert_eq!(&"disabled".to_owned(), svc.server_error());
+    }
+}
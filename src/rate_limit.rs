@@ -0,0 +1,150 @@
+use std::fmt;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{ Duration, Instant };
+
+use super::{ Endpoint, HealthStatus, Service, ServiceResult };
+
+#[derive(Clone, Debug, PartialEq)]
+/// Controls how many calls a RateLimitedService lets through per interval
+pub struct RateLimit {
+    /// Calls that may be made back to back before throttling starts
+    pub capacity: u32,
+    /// The interval over which a full capacity of calls is refilled, at an even rate
+    pub interval: Duration,
+}
+
+impl RateLimit {
+    pub fn new(capacity: u32, interval: Duration) -> Self {
+        RateLimit { capacity, interval }
+    }
+
+    /// How long it takes to refill a single call
+    fn per_token(&self) -> Duration {
+        self.interval / self.capacity.max(1)
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// RateLimitedService decorates an inner service with a token bucket, starting full at the
+/// configured capacity and refilling evenly over the interval. A call made while the bucket is
+/// empty sleeps the calling thread until a token is available before being delegated.
+pub struct RateLimitedService<S> {
+    inner: S,
+    limit: RateLimit,
+    bucket: Mutex<Bucket>,
+}
+
+impl<S> RateLimitedService<S>
+where
+    S: Service,
+{
+    pub fn new(inner: S, limit: RateLimit) -> Self {
+        RateLimitedService {
+            inner,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(limit.capacity),
+                refilled_at: Instant::now(),
+            }),
+            limit,
+        }
+    }
+
+    pub fn limit(&self) -> &RateLimit {
+        &self.limit
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Takes a token from the bucket, returning how long the caller must wait for it.
+    /// The token is reserved up front so concurrent callers queue behind each other.
+    fn acquire(&self) -> Duration {
+        let per_token = self.limit.per_token();
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at);
+        let refilled = elapsed.as_secs_f64() / per_token.as_secs_f64();
+        bucket.tokens = (bucket.tokens + refilled).min(f64::from(self.limit.capacity));
+        bucket.refilled_at = now;
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            per_token.mul_f64(-bucket.tokens)
+        }
+    }
+}
+
+impl<S> Service for RateLimitedService<S>
+where
+    S: Service,
+{
+    type TRequestType = S::TRequestType;
+    type TServiceError = S::TServiceError;
+    type TErrorSerde = S::TErrorSerde;
+
+    fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, Self::TServiceError, Self::TErrorSerde>
+    where
+        TRequest: Into<Self::TRequestType> + Endpoint + fmt::Debug,
+    {
+        let wait = self.acquire();
+        if wait > Duration::from_secs(0) {
+            thread::sleep(wait);
+        }
+        self.inner.exec(req)
+    }
+
+    fn health(&self) -> HealthStatus {
+        self.inner.health()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ RateLimit, RateLimitedService, Service };
+    use pretty_assertions::{ assert_eq };
+    use std::time::{ Duration, Instant };
+    use test_support::{ alice, mock_user, GetUser };
+    use MockService;
+
+    fn limited() -> RateLimitedService<MockService<GetUser, String>> {
+        RateLimitedService::new(mock_user(alice()), RateLimit::new(2, Duration::from_millis(100)))
+    }
+
+    #[test]
+    fn calls_within_capacity_are_not_throttled() {
+        let svc = limited();
+        let started = Instant::now();
+
+        assert!(svc.exec(GetUser { id: 1 }).is_ok());
+        assert!(svc.exec(GetUser { id: 1 }).is_ok());
+
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn calls_beyond_capacity_are_throttled() {
+        let svc = limited();
+        let started = Instant::now();
+
+        for _ in 0..4 {
+            assert!(svc.exec(GetUser { id: 1 }).is_ok());
+        }
+
+        assert!(started.elapsed() >= Duration::from_millis(90));
+        assert_eq!(4, svc.inner().requests().len());
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+
+use super::{ Endpoint, Service, ServiceError, ServiceResult };
+
+/// Retryable classifies whether a service error represents a transient condition worth retrying.
+/// ServiceError implementors without a classification of their own can delegate to
+/// `retryable_by_status`.
+pub trait Retryable {
+    fn is_retryable(&self) -> bool;
+}
+
+/// Server side and throttling statuses are considered transient, errors without a status are not
+pub fn retryable_by_status<T: ServiceError>(err: &T) -> bool {
+    match err.status() {
+        Some(status) => status >= 500 || status == 429,
+        None => false,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Controls how often and how patiently a RetryService re-issues failed calls
+pub struct RetryPolicy {
+    /// Total number of calls made for a request, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each retry after it
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        RetryPolicy {
+            max_attempts,
+            base_delay,
+        }
+    }
+
+    /// The delay to wait after the given (1 based) failed attempt
+    pub fn delay(&self, attempt: u32) -> Duration {
+        2u32.checked_pow(attempt.saturating_sub(1))
+            .and_then(|factor| self.base_delay.checked_mul(factor))
+            .unwrap_or(Duration::MAX)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3, Duration::from_millis(100))
+    }
+}
+
+/// RetryService decorates an inner service, re-issuing a request with exponential backoff while it
+/// results in a Fail whose server error is retryable. Ok and Err results are returned immediately as a
+/// parsed error is not transient.
+/// Retrying needs to clone the request, which Service::exec cannot require, so RetryService exposes
+/// its own exec rather than implementing Service.
+pub struct RetryService<S> {
+    inner: S,
+    policy: RetryPolicy,
+}
+
+impl<S> RetryService<S>
+where
+    S: Service,
+    S::TServiceError: Retryable,
+{
+    pub fn new(inner: S, policy: RetryPolicy) -> Self {
+        RetryService { inner, policy }
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Executes the request against the inner service, sleeping the calling thread between attempts
+    pub fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, S::TServiceError, S::TErrorSerde>
+    where
+        TRequest: Into<S::TRequestType> + Endpoint + fmt::Debug + Clone,
+    {
+        let mut attempt = 1;
+        loop {
+            let result = self.inner.exec(req.clone());
+            let retryable = match &result {
+                ServiceResult::Fail(svc_err, _) => svc_err.is_retryable(),
+                _ => false,
+            };
+            if !retryable || attempt >= self.policy.max_attempts {
+                return result;
+            }
+            thread::sleep(self.policy.delay(attempt));
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ retryable_by_status, RetryPolicy, RetryService, Retryable, ServiceError };
+    use pretty_assertions::{ assert_eq };
+    use std::time::Duration;
+    use test_support::{ alice, GetUser, TestError };
+    use { MockResponse, MockService };
+
+    fn retrying(max_attempts: u32) -> RetryService<MockService<GetUser, TestError>> {
+        let policy = RetryPolicy::new(max_attempts, Duration::from_millis(1));
+        RetryService::new(MockService::new(), policy)
+    }
+
+    #[test]
+    fn delay_doubles_per_attempt() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(10));
+        assert_eq!(Duration::from_millis(10), policy.delay(1));
+        assert_eq!(Duration::from_millis(20), policy.delay(2));
+        assert_eq!(Duration::from_millis(40), policy.delay(3));
+    }
+
+    #[test]
+    fn retries_fail_until_ok() {
+        let svc = retrying(3);
+        let req = GetUser { id: 1 };
+        svc.inner().respond(&req, MockResponse::Fail(TestError::Timeout));
+        svc.inner().respond(&req, MockResponse::Fail(TestError::Timeout));
+        svc.inner().respond(&req, alice());
+
+        let result = svc.exec(req);
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+        assert_eq!(3, svc.inner().requests().len());
+    }
+
+    #[test]
+    fn stops_at_max_attempts() {
+        let svc = retrying(2);
+        let req = GetUser { id: 1 };
+        svc.inner().respond(&req, MockResponse::Fail(TestError::Timeout));
+
+        let result = svc.exec(req);
+
+        assert!(result.is_fail());
+        assert_eq!(2, svc.inner().requests().len());
+    }
+
+    #[test]
+    fn does_not_retry_err() {
+        let svc = retrying(3);
+        let req = GetUser { id: 1 };
+        svc.inner().respond(&req, MockResponse::Err(TestError::Unauthorized, "\"missing\"".to_owned()));
+
+        let result = svc.exec(req);
+
+        assert!(result.is_err());
+        assert_eq!(1, svc.inner().requests().len());
+    }
+
+    #[test]
+    fn stops_retrying_at_first_non_retryable_fail() {
+        let svc = retrying(4);
+        let req = GetUser { id: 1 };
+        svc.inner().respond(&req, MockResponse::Fail(TestError::Timeout));
+        svc.inner().respond(&req, MockResponse::Fail(TestError::Unauthorized));
+        svc.inner().respond(&req, alice());
+
+        let result = svc.exec(req);
+
+        assert_eq!(Some(&TestError::Unauthorized), result.server_error());
+        assert_eq!(2, svc.inner().requests().len());
+    }
+
+    #[test]
+    fn does_not_retry_non_retryable_fail() {
+        let svc = retrying(3);
+        let req = GetUser { id: 1 };
+        svc.inner().respond(&req, MockResponse::Fail(TestError::Unauthorized));
+        svc.inner().respond(&req, alice());
+
+        let result = svc.exec(req);
+
+        assert_eq!(Some(&TestError::Unauthorized), result.server_error());
+        assert_eq!(1, svc.inner().requests().len());
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct UpstreamError {
+        status: Option<u16>,
+        message: String,
+    }
+
+    impl UpstreamError {
+        fn new(status: Option<u16>, message: &str) -> Self {
+            UpstreamError {
+                status,
+                message: message.to_owned(),
+            }
+        }
+    }
+
+    impl ServiceError for UpstreamError {
+        fn status(&self) -> Option<u16> {
+            self.status
+        }
+
+        fn message(&self) -> &str {
+            &self.message
+        }
+    }
+
+    impl Retryable for UpstreamError {
+        fn is_retryable(&self) -> bool {
+            retryable_by_status(self)
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct GatewayError {
+        status: u16,
+    }
+
+    impl ServiceError for GatewayError {
+        fn status(&self) -> Option<u16> {
+            Some(self.status)
+        }
+
+        fn message(&self) -> &str {
+            "gateway"
+        }
+    }
+
+    impl Retryable for GatewayError {
+        /// Only a gateway timeout is worth retrying against this backend
+        fn is_retryable(&self) -> bool {
+            self.status == 504
+        }
+    }
+
+    #[test]
+    fn service_errors_can_classify_themselves() {
+        assert!(GatewayError { status: 504 }.is_retryable());
+        assert!(!GatewayError { status: 503 }.is_retryable());
+        assert!(retryable_by_status(&GatewayError { status: 503 }));
+    }
+
+    #[test]
+    fn classifies_service_errors_by_status() {
+        assert!(UpstreamError::new(Some(503), "unavailable").is_retryable());
+        assert!(UpstreamError::new(Some(429), "slow down").is_retryable());
+        assert!(!UpstreamError::new(Some(404), "missing").is_retryable());
+        assert!(!UpstreamError::new(None, "refused").is_retryable());
+        assert_eq!("refused", UpstreamError::new(None, "refused").message());
+    }
+
+    #[test]
+    fn retries_service_error_with_server_status() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(1));
+        let svc = RetryService::new(MockService::<GetUser, UpstreamError>::new(), policy);
+        let req = GetUser { id: 1 };
+        let unavailable = UpstreamError::new(Some(503), "unavailable");
+        svc.inner().respond(&req, MockResponse::Fail(unavailable));
+        svc.inner().respond(&req, alice());
+
+        let result = svc.exec(req);
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+        assert_eq!(2, svc.inner().requests().len());
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::{ Mutex, Once };
+
+use log::{ Level, LevelFilter, Log, Metadata, Record };
+
+/// Captures every log record so tests can assert on emitted lines.
+/// log only allows a single logger per process, so all tests share this one.
+struct CapturingLogger {
+    records: Mutex<Vec<(Level, String)>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let line = format!("{}", record.args());
+        self.records.lock().unwrap().push((record.level(), line));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    records: Mutex::new(Vec::new()),
+};
+
+static INIT: Once = Once::new();
+
+/// Installs the capturing logger, safe to call from every test that needs it
+pub fn init() {
+    INIT.call_once(|| {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+    });
+}
+
+/// The captured lines containing `needle`, as tests run in parallel and share the logger
+pub fn lines_containing(needle: &str) -> Vec<(Level, String)> {
+    LOGGER
+        .records
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, line)| line.contains(needle))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+#[cfg(feature = "wiremock-enabled")]
+use std::sync::{ Mutex, MutexGuard };
+use std::time::Duration;
+
+use { CircuitOpen, Endpoint, MockResponse, MockService, Retryable };
+use { Service, ServiceError, ServiceResult, TimedOut };
+
+/// The timeout GetUser declares, generous for a mock or local server yet quick to overrun
+pub const GET_USER_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[cfg(feature = "wiremock-enabled")]
+static WIREMOCK_BASE_LOCK: Mutex<()> = Mutex::new(());
+
+#[cfg(feature = "wiremock-enabled")]
+/// Serialises the tests that set the process wide wiremock base so they can't observe each other's base
+pub fn lock_wiremock_base() -> MutexGuard<'static, ()> {
+    WIREMOCK_BASE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[derive(Clone, Debug)]
+/// The request the module tests execute, fetching a single user
+pub struct GetUser {
+    pub id: u32,
+}
+
+impl Endpoint for GetUser {
+    type TResponse = String;
+    type TError = String;
+
+    fn path(&self) -> String {
+        format!("/users/{}", self.id)
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(GET_USER_TIMEOUT)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A server error covering the outcomes the decorators classify.
+/// Only a Timeout is retryable, and only Unauthorized and NotFound carry a status.
+pub enum TestError {
+    Timeout,
+    Unauthorized,
+    NotFound,
+    CircuitOpen,
+}
+
+impl ServiceError for TestError {
+    fn status(&self) -> Option<u16> {
+        match self {
+            TestError::Unauthorized => Some(401),
+            TestError::NotFound => Some(404),
+            TestError::Timeout | TestError::CircuitOpen => None,
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            TestError::Timeout => "timeout",
+            TestError::Unauthorized => "unauthorized",
+            TestError::NotFound => "not found",
+            TestError::CircuitOpen => "circuit open",
+        }
+    }
+}
+
+impl Retryable for TestError {
+    fn is_retryable(&self) -> bool {
+        *self == TestError::Timeout
+    }
+}
+
+impl CircuitOpen for TestError {
+    fn circuit_open() -> Self {
+        TestError::CircuitOpen
+    }
+}
+
+impl TimedOut for TestError {
+    fn timed_out() -> Self {
+        TestError::Timeout
+    }
+}
+
+/// The Ok response carrying the user alice
+pub fn alice<TServiceError>() -> MockResponse<TServiceError> {
+    MockResponse::Ok("\"alice\"".to_owned())
+}
+
+/// A mock answering `GetUser { id: 1 }` with the response
+pub fn mock_user<TServiceError>(
+    response: MockResponse<TServiceError>,
+) -> MockService<GetUser, TServiceError> {
+    let svc = MockService::new();
+    svc.respond(&GetUser { id: 1 }, response);
+    svc
+}
+
+/// Executes the request through the Service trait, the way generic callers see the service
+pub fn call<S, TRequest>(
+    svc: &S,
+    req: TRequest,
+) -> ServiceResult<TRequest, S::TServiceError, S::TErrorSerde>
+where
+    S: Service,
+    TRequest: Into<S::TRequestType> + Endpoint + fmt::Debug,
+{
+    svc.exec(req)
+}
@@ -0,0 +1,199 @@
+use std::fmt;
+use std::sync::mpsc::{ self, RecvTimeoutError };
+use std::sync::Arc;
+use std::thread;
+
+use super::{ Endpoint, Service, ServiceResult };
+
+/// TimedOut lets TimeoutService synthesize the server error of a call that overran its timeout
+pub trait TimedOut {
+    fn timed_out() -> Self;
+}
+
+/// TimeoutService decorates an inner service, enforcing `Endpoint::timeout` on the sync path.
+/// Each call with a timeout is executed on a newly spawned worker thread while the caller waits up
+/// to the timeout, failing with `TimedOut::timed_out()` once it elapses. The worker is not stopped
+/// and runs the abandoned call to completion in the background.
+/// Spawning a thread per call costs far more than the call itself for fast backends, so endpoints
+/// without a timeout are executed on the calling thread. Running on a worker requires the request,
+/// its outcome and the inner service to be Send and 'static, which Service::exec cannot require, so
+/// TimeoutService exposes its own exec rather than implementing Service.
+pub struct TimeoutService<S> {
+    inner: Arc<S>,
+}
+
+impl<S> TimeoutService<S>
+where
+    S: Service,
+{
+    pub fn new(inner: S) -> Self {
+        TimeoutService {
+            inner: Arc::new(inner),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Executes the request against the inner service, failing if it does not finish within the
+    /// endpoint's timeout. Panics if the inner service panics while the caller is still waiting.
+    pub fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, S::TServiceError, S::TErrorSerde>
+    where
+        S: Send + Sync + 'static,
+        S::TServiceError: TimedOut + Send + 'static,
+        S::TErrorSerde: Send + 'static,
+        TRequest: Into<S::TRequestType> + Endpoint + fmt::Debug + Send + 'static,
+        TRequest::TResponse: Send + 'static,
+        TRequest::TError: Send + 'static,
+    {
+        let timeout = match req.timeout() {
+            Some(timeout) => timeout,
+            None => return self.inner.exec(req),
+        };
+        let inner = Arc::clone(&self.inner);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // The caller may have given up already, in which case nobody is left to receive
+            let _ = sender.send(inner.exec(req));
+        });
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                ServiceResult::Fail(S::TServiceError::timed_out(), None)
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                panic!("TimeoutService inner service panicked before responding")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TimeoutService;
+    use pretty_assertions::{ assert_eq };
+    use std::fmt;
+    use std::thread;
+    use std::time::Duration;
+    use test_support::{ alice, mock_user, GetUser, TestError, GET_USER_TIMEOUT };
+    use { Endpoint, MockService, Service, ServiceResult };
+
+    /// Sleeps before delegating to the wrapped mock to simulate a slow backend
+    struct SlowService {
+        delay: Duration,
+        inner: MockService<GetUser, TestError>,
+    }
+
+    impl Service for SlowService {
+        type TRequestType = GetUser;
+        type TServiceError = TestError;
+        type TErrorSerde = serde_json::Error;
+
+        fn exec<TRequest>(
+            &self,
+            req: TRequest,
+        ) -> ServiceResult<TRequest, Self::TServiceError, Self::TErrorSerde>
+        where
+            TRequest: Into<Self::TRequestType> + Endpoint + fmt::Debug,
+        {
+            thread::sleep(self.delay);
+            self.inner.exec(req)
+        }
+    }
+
+    fn slow(delay: Duration) -> TimeoutService<SlowService> {
+        TimeoutService::new(SlowService {
+            delay,
+            inner: mock_user(alice()),
+        })
+    }
+
+    #[test]
+    fn fails_when_inner_overruns_timeout() {
+        let svc = slow(GET_USER_TIMEOUT * 3);
+
+        let result = svc.exec(GetUser { id: 1 });
+
+        assert!(result.is_fail());
+        assert_eq!(Some(&TestError::Timeout), result.server_error());
+    }
+
+    #[test]
+    fn returns_inner_result_within_timeout() {
+        let svc = slow(Duration::from_millis(0));
+
+        let result = svc.exec(GetUser { id: 1 });
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+    }
+
+    #[cfg(feature = "wiremock-enabled")]
+    /// Records the url each request resolves to, on whichever thread executes it
+    struct RecordingService {
+        urls: std::sync::Mutex<Vec<String>>,
+        inner: MockService<GetUser, TestError>,
+    }
+
+    #[cfg(feature = "wiremock-enabled")]
+    impl Service for RecordingService {
+        type TRequestType = GetUser;
+        type TServiceError = TestError;
+        type TErrorSerde = serde_json::Error;
+
+        fn exec<TRequest>(
+            &self,
+            req: TRequest,
+        ) -> ServiceResult<TRequest, Self::TServiceError, Self::TErrorSerde>
+        where
+            TRequest: Into<Self::TRequestType> + Endpoint + fmt::Debug,
+        {
+            let url = req.resolve("https://api.example.com").unwrap();
+            self.urls.lock().unwrap().push(url.as_str().to_owned());
+            self.inner.exec(req)
+        }
+    }
+
+    #[cfg(feature = "wiremock-enabled")]
+    #[test]
+    fn resolves_against_wiremock_base_on_worker_thread() {
+        use { clear_wiremock_base, set_wiremock_base };
+        let _lock = ::test_support::lock_wiremock_base();
+        set_wiremock_base("http://127.0.0.1:34567").unwrap();
+        let svc = TimeoutService::new(RecordingService {
+            urls: std::sync::Mutex::new(Vec::new()),
+            inner: mock_user(alice()),
+        });
+
+        let result = svc.exec(GetUser { id: 1 });
+        clear_wiremock_base();
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+        let urls = svc.inner().urls.lock().unwrap().clone();
+        assert_eq!(vec!["http://127.0.0.1:34567/users/1".to_owned()], urls);
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-enabled", feature = "mockito-enabled"))]
+mod http_test {
+    use super::TimeoutService;
+    use pretty_assertions::{ assert_eq };
+    use test_support::GetUser;
+    use HttpService;
+
+    #[test]
+    fn executes_http_service_within_timeout() {
+        let _mock = mockito::mock("GET", "/users/21")
+            .with_status(200)
+            .with_body("\"alice\"")
+            .create();
+        let svc = TimeoutService::new(HttpService::new("https://api.example.com"));
+
+        let result = svc.exec(GetUser { id: 21 });
+
+        assert_eq!(Some("alice".to_owned()), result.ok());
+    }
+}
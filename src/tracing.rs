@@ -0,0 +1,117 @@
+use std::fmt;
+use std::time::Instant;
+
+use super::{ Endpoint, HealthStatus, Service, ServiceResult };
+
+/// TracingService decorates an inner service, logging each request at debug level before it is
+/// executed and the outcome with the elapsed time afterwards: Ok at info, Err and Fail at warn.
+pub struct TracingService<S> {
+    inner: S,
+}
+
+impl<S> TracingService<S>
+where
+    S: Service,
+{
+    pub fn new(inner: S) -> Self {
+        TracingService { inner }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Service for TracingService<S>
+where
+    S: Service,
+    S::TServiceError: fmt::Debug,
+{
+    type TRequestType = S::TRequestType;
+    type TServiceError = S::TServiceError;
+    type TErrorSerde = S::TErrorSerde;
+
+    fn exec<TRequest>(
+        &self,
+        req: TRequest,
+    ) -> ServiceResult<TRequest, Self::TServiceError, Self::TErrorSerde>
+    where
+        TRequest: Into<Self::TRequestType> + Endpoint + fmt::Debug,
+    {
+        let request = format!("{:?}", req);
+        debug!("exec {}", request);
+        let started = Instant::now();
+        let result = self.inner.exec(req);
+        let elapsed = started.elapsed();
+        match &result {
+            ServiceResult::Ok(_) => info!("exec {} -> Ok in {:?}", request, elapsed),
+            ServiceResult::Err(svc_err, _) => {
+                warn!("exec {} -> Err({:?}) in {:?}", request, svc_err, elapsed)
+            }
+            ServiceResult::Fail(svc_err, _) => {
+                warn!("exec {} -> Fail({:?}) in {:?}", request, svc_err, elapsed)
+            }
+        }
+        result
+    }
+
+    fn health(&self) -> HealthStatus {
+        self.inner.health()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ Endpoint, Service, TracingService };
+    use log::Level;
+    use pretty_assertions::{ assert_eq };
+    use test_logger;
+    use { MockResponse, MockService };
+
+    #[derive(Debug)]
+    struct TracedUser {
+        id: u32,
+    }
+
+    impl Endpoint for TracedUser {
+        type TResponse = String;
+        type TError = String;
+
+        fn path(&self) -> String {
+            format!("/users/{}", self.id)
+        }
+    }
+
+    #[test]
+    fn logs_request_and_ok_outcome() {
+        test_logger::init();
+        let svc = TracingService::new(MockService::<TracedUser, String>::new());
+        svc.inner().respond(&TracedUser { id: 1 }, MockResponse::Ok("\"alice\"".to_owned()));
+
+        svc.exec(TracedUser { id: 1 });
+
+        let lines = test_logger::lines_containing("TracedUser { id: 1 }");
+        assert_eq!(2, lines.len());
+        assert_eq!((Level::Debug, "exec TracedUser { id: 1 }".to_owned()), lines[0]);
+        assert_eq!(Level::Info, lines[1].0);
+        assert!(lines[1].1.starts_with("exec TracedUser { id: 1 } -> Ok in "));
+    }
+
+    #[test]
+    fn logs_fail_outcome_with_server_error() {
+        test_logger::init();
+        let svc = TracingService::new(MockService::<TracedUser, String>::new());
+        svc.inner().respond(&TracedUser { id: 2 }, MockResponse::Fail("timeout".to_owned()));
+
+        svc.exec(TracedUser { id: 2 });
+
+        let lines = test_logger::lines_containing("TracedUser { id: 2 }");
+        assert_eq!(2, lines.len());
+        assert_eq!(Level::Warn, lines[1].0);
+        assert!(lines[1].1.starts_with("exec TracedUser { id: 2 } -> Fail(\"timeout\") in "));
+    }
+}